@@ -0,0 +1,71 @@
+//! Library entry points for embedding the animation renderer in other GL/Skia apps.
+//!
+//! Unlike the `lottie-viewer` binary, `TextureTarget` and `render_frame_to_texture` let a
+//! host application render frames directly into its own texture/FBO.
+
+extern crate skia_safe as skia;
+
+use skia::gpu::gl::FramebufferInfo;
+use skia::gpu::{BackendRenderTarget, SurfaceOrigin};
+use skia::{ColorType, Surface};
+
+// A GL texture, wrapped as a Skia render target, that `render_frame_to_texture` renders
+// animation frames into. Must be created (and used) on a GL context current with `gr_context`.
+pub struct TextureTarget {
+    surface: Surface,
+    texture_id: u32,
+}
+
+impl TextureTarget {
+    // Wraps an app-provided GL texture/FBO as a Skia render target. `fb_info`/`texture_id`
+    // describe the caller's FBO; `sample_count`/`stencil_bits` must match how it was actually
+    // allocated (e.g. `0`/`0` for a plain color-only FBO), since Lottie's clip-path rendering
+    // relies on an adequate stencil buffer being present.
+    pub fn new(
+        gr_context: &mut skia::gpu::Context,
+        fb_info: FramebufferInfo,
+        texture_id: u32,
+        width: i32,
+        height: i32,
+        sample_count: usize,
+        stencil_bits: usize,
+    ) -> Option<Self> {
+        let backend_render_target =
+            BackendRenderTarget::new_gl((width, height), Some(sample_count), stencil_bits, fb_info);
+
+        let surface = Surface::from_backend_render_target(
+            gr_context,
+            &backend_render_target,
+            SurfaceOrigin::TopLeft,
+            ColorType::RGBA8888,
+            skia::ColorSpace::new_srgb(),
+            None,
+        )?;
+
+        Some(TextureTarget {
+            surface,
+            texture_id,
+        })
+    }
+
+    /// The GL texture id backing this target.
+    pub fn texture_id(&self) -> u32 {
+        self.texture_id
+    }
+}
+
+// Seeks `animation` to `time`, renders it into `target`, and returns its texture id.
+pub fn render_frame_to_texture(
+    animation: &mut skia::animation::Animation,
+    time: f64,
+    target: &mut TextureTarget,
+) -> u32 {
+    animation.seek_time::<()>(time);
+
+    let canvas = target.surface.canvas();
+    canvas.clear(0x00_00_00_00);
+    animation.render(canvas, None);
+    canvas.flush();
+
+    target.texture_id
+}
@@ -1,23 +1,281 @@
 extern crate skia_safe as skia;
 
+mod overlay;
+
 use clap::{App, Arg};
 use either::Either;
-use glutin::dpi::LogicalSize;
+use glutin::config::ConfigTemplateBuilder;
+use glutin::context::{
+    ContextApi, ContextAttributesBuilder, NotCurrentContext, NotCurrentGlContext,
+    PossiblyCurrentContext, PossiblyCurrentGlContext,
+};
+use glutin::display::{GetGlDisplay, GlDisplay};
+use glutin::surface::{GlSurface, SurfaceAttributesBuilder, SwapInterval, WindowSurface};
+use glutin_winit::DisplayBuilder;
+use gst::prelude::*;
+use gstreamer as gst;
+use gstreamer_app as gst_app;
+use raw_window_handle::HasRawWindowHandle;
 #[cfg(windows)]
-use glutin::platform::windows::WindowBuilderExtWindows;
-use glutin::{
-    event::{Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+use winit::platform::windows::WindowBuilderExtWindows;
+use winit::{
+    dpi::LogicalSize,
+    event::{ElementState, Event, KeyboardInput, MouseButton, VirtualKeyCode, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
-    window::WindowBuilder,
-    ContextBuilder, GlRequest,
+    window::{Fullscreen, Window, WindowBuilder},
 };
+use overlay::{Overlay, OverlayProps};
 use skia::{
     gpu::{gl::FramebufferInfo, BackendRenderTarget, SurfaceOrigin},
-    ColorType, Surface,
+    ColorType, EncodedImageFormat, Surface,
 };
-use std::{convert::TryInto, time};
+use std::{convert::TryInto, ffi::CString, num::NonZeroU32, path::Path, time};
+
+type Renderable = Either<skia::animation::Animation, skia::svg::SvgDom>;
+
+// Current position, speed and pause/loop state of interactive playback.
+struct Playback {
+    time: f64,
+    speed: f64,
+    paused: bool,
+    looping: bool,
+}
+
+impl Playback {
+    fn new() -> Self {
+        Playback {
+            time: 0.0,
+            speed: 1.0,
+            paused: false,
+            looping: true,
+        }
+    }
+
+    // Moves the clock forward by `dt * speed` seconds, wrapping or clamping to `duration`.
+    fn advance(&mut self, dt: f64, duration: f64) {
+        if self.paused || duration <= 0.0 {
+            return;
+        }
+        self.seek(self.time + dt * self.speed, duration);
+    }
+
+    fn seek(&mut self, time: f64, duration: f64) {
+        self.time = if self.looping {
+            time.rem_euclid(duration.max(f64::MIN_POSITIVE))
+        } else {
+            time.clamp(0.0, duration)
+        };
+    }
+
+    fn step_frames(&mut self, frames: f64, fps: f64, duration: f64) {
+        self.seek(self.time + frames / fps, duration);
+    }
+
+    fn nudge_speed(&mut self, delta: f64) {
+        self.speed = (self.speed + delta).clamp(0.1, 8.0);
+    }
+}
+
+fn load_renderable(filename: &Path) -> Renderable {
+    let mut file = std::fs::File::open(filename).unwrap();
+    match filename
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .as_ref()
+        .map(|s| &s[..])
+    {
+        Some("json") | Some("lottie") => skia::animation::Animation::read(&mut file)
+            .map(Either::Left)
+            .expect("Failed to open lottie file"),
+        Some("svg") => skia::svg::SvgDom::read(&mut file)
+            .map(Either::Right)
+            .expect("Failed to open lottie file"),
+        other => panic!("Unrecognized filetype: {:?}", other),
+    }
+}
+
+// A dropped file can be anything the user dragged in, so unlike the startup/CLI paths (where a
+// bad path/format is a usage error worth failing fast on), a bad drop shouldn't take down the
+// whole viewer. `load_renderable` panics on a bad file; catch that here and keep whatever was
+// already playing.
+fn try_load_dropped_file(filename: &Path) -> Result<Renderable, String> {
+    std::panic::catch_unwind(|| load_renderable(filename)).map_err(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "Failed to load dropped file".to_string())
+    })
+}
+
+// Substitutes a `%0Nd`/`%d` placeholder in `pattern` with `frame`.
+fn format_frame_path(pattern: &str, frame: usize) -> String {
+    if let Some(start) = pattern.find('%') {
+        if let Some(d_offset) = pattern[start..].find('d') {
+            let spec = &pattern[start..start + d_offset + 1];
+            let width: usize = spec[1..spec.len() - 1]
+                .trim_start_matches('0')
+                .parse()
+                .unwrap_or(0);
+            let formatted = if width > 0 {
+                format!("{:0width$}", frame, width = width)
+            } else {
+                frame.to_string()
+            };
+            return pattern.replacen(spec, &formatted, 1);
+        }
+    }
+    pattern.to_string()
+}
+
+// Renders every frame of `filename` at `fps` into a shared raster surface, calling `f`
+// with the frame index and the freshly-rendered surface. Shared by `--export` and `--encode`.
+fn render_frames(
+    filename: &Path,
+    fps: f64,
+    width: i32,
+    height: i32,
+    mut f: impl FnMut(usize, &mut Surface),
+) {
+    let mut to_render = load_renderable(filename);
+
+    let duration = match &to_render {
+        Either::Left(animation) => animation.duration(),
+        Either::Right(_) => 0.0,
+    };
+
+    let num_frames = (duration * fps).ceil().max(1.0) as usize;
+
+    let mut surface = Surface::new_raster_n32_premul((width, height))
+        .expect("Failed to create offscreen raster surface");
+
+    for frame in 0..num_frames {
+        let t = (frame as f64 / fps).min(duration);
+
+        if let Either::Left(animation) = &mut to_render {
+            animation.seek_time::<()>(t);
+        }
+
+        let canvas = surface.canvas();
+        canvas.clear(0xff_ff_ff_ff);
+
+        match &to_render {
+            Either::Left(animation) => animation.render(canvas, None),
+            Either::Right(svg) => svg.render(canvas),
+        }
+
+        canvas.flush();
+
+        f(frame, &mut surface);
+    }
+}
+
+// Renders every frame of `filename` at `fps` into a PNG, following `pattern` (e.g. `frame_%04d.png`).
+fn export_frames(filename: &Path, pattern: &str, fps: f64, width: i32, height: i32) {
+    render_frames(filename, fps, width, height, |frame, surface| {
+        let data = surface
+            .image_snapshot()
+            .encode_to_data(EncodedImageFormat::PNG)
+            .expect("Failed to encode frame to PNG");
 
-type WindowedContext = glutin::ContextWrapper<glutin::PossiblyCurrent, glutin::window::Window>;
+        let out_path = format_frame_path(pattern, frame);
+        if let Some(parent) = Path::new(&out_path).parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create export directory");
+        }
+        std::fs::write(&out_path, data.as_bytes())
+            .unwrap_or_else(|e| panic!("Failed to write frame {}: {}", out_path, e));
+    });
+}
+
+// Picks an encoder/muxer pair from `out`'s extension: `vp9enc`/`webmmux` for `.webm`, `x264enc`/`mp4mux` otherwise.
+fn encoder_and_muxer_for(out: &Path) -> (&'static str, &'static str) {
+    match out.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("webm") => ("vp9enc", "webmmux"),
+        _ => ("x264enc", "mp4mux"),
+    }
+}
+
+// Renders `filename` and pushes each frame's BGRA pixels into a GStreamer
+// `appsrc ! videoconvert ! <encoder> ! <muxer> ! filesink` pipeline, writing the result to `out`.
+fn encode_video(filename: &Path, out: &Path, fps: f64, width: i32, height: i32) {
+    gst::init().expect("Failed to initialize GStreamer");
+
+    let (encoder, muxer) = encoder_and_muxer_for(out);
+    let pipeline = gst::parse_launch(&format!(
+        "appsrc name=src is-live=true format=time block=true ! videoconvert ! {} ! {} ! filesink location=\"{}\"",
+        encoder,
+        muxer,
+        out.display(),
+    ))
+    .expect("Failed to build encode pipeline")
+    .downcast::<gst::Pipeline>()
+    .unwrap();
+
+    let appsrc = pipeline
+        .by_name("src")
+        .unwrap()
+        .downcast::<gst_app::AppSrc>()
+        .unwrap();
+
+    appsrc.set_caps(Some(
+        &gst::Caps::builder("video/x-raw")
+            .field("format", "BGRA")
+            .field("width", width)
+            .field("height", height)
+            .field("framerate", gst::Fraction::new((fps.round() as i32).max(1), 1))
+            .build(),
+    ));
+
+    pipeline
+        .set_state(gst::State::Playing)
+        .expect("Failed to start encode pipeline");
+
+    let frame_duration = gst::ClockTime::from_nseconds((1_000_000_000.0 / fps).round() as u64);
+    let image_info =
+        skia::ImageInfo::new((width, height), ColorType::BGRA8888, skia::AlphaType::Premul, None);
+    let row_bytes = width as usize * 4;
+
+    render_frames(filename, fps, width, height, |frame, surface| {
+        let mut pixels = vec![0u8; row_bytes * height as usize];
+        let ok = surface
+            .canvas()
+            .read_pixels(&image_info, &mut pixels, row_bytes, (0, 0));
+        assert!(ok, "Failed to read back rendered frame {}", frame);
+
+        let mut buffer = gst::Buffer::from_mut_slice(pixels);
+        {
+            let buffer = buffer.get_mut().unwrap();
+            buffer.set_pts(frame_duration * frame as u64);
+            buffer.set_duration(frame_duration);
+        }
+
+        appsrc
+            .push_buffer(buffer)
+            .expect("Failed to push frame to encode pipeline");
+    });
+
+    appsrc
+        .end_of_stream()
+        .expect("Failed to signal end of stream to encode pipeline");
+
+    let bus = pipeline.bus().expect("Encode pipeline has no bus");
+    for msg in bus.iter_timed(gst::ClockTime::NONE) {
+        match msg.view() {
+            gst::MessageView::Eos(_) => break,
+            gst::MessageView::Error(err) => panic!(
+                "Encode pipeline error from {:?}: {} ({:?})",
+                err.src().map(|s| s.path_string()),
+                err.error(),
+                err.debug(),
+            ),
+            _ => {}
+        }
+    }
+
+    pipeline
+        .set_state(gst::State::Null)
+        .expect("Failed to stop encode pipeline");
+}
 
 fn main() {
     const WIDTH: usize = 800;
@@ -30,34 +288,192 @@ fn main() {
                 .required(true)
                 .index(1),
         )
+        .arg(
+            Arg::with_name("export")
+                .long("export")
+                .value_name("PATTERN")
+                .takes_value(true)
+                .help("Render frames to PNGs instead of opening a window, e.g. frame_%04d.png"),
+        )
+        .arg(
+            Arg::with_name("fps")
+                .long("fps")
+                .value_name("FPS")
+                .takes_value(true)
+                .default_value("60")
+                .help("Frame rate to use when exporting"),
+        )
+        .arg(
+            Arg::with_name("width")
+                .long("width")
+                .value_name("WIDTH")
+                .takes_value(true)
+                .default_value("800")
+                .help("Output width in pixels when exporting"),
+        )
+        .arg(
+            Arg::with_name("height")
+                .long("height")
+                .value_name("HEIGHT")
+                .takes_value(true)
+                .default_value("600")
+                .help("Output height in pixels when exporting"),
+        )
+        .arg(
+            Arg::with_name("vsync")
+                .long("vsync")
+                .help("Synchronize frame presentation to the display's refresh rate"),
+        )
+        .arg(
+            Arg::with_name("encode")
+                .long("encode")
+                .value_name("OUTPUT")
+                .takes_value(true)
+                .conflicts_with("export")
+                .help("Encode playback directly to a video file (.mp4/.webm) instead of opening a window"),
+        )
+        .arg(
+            Arg::with_name("overlay")
+                .long("overlay")
+                .help("Show an on-canvas play/pause, scrubber and drag-and-drop control overlay"),
+        )
         .get_matches();
     let filename = std::path::Path::new(matches.value_of_os("INPUT").unwrap());
 
+    if let Some(pattern) = matches.value_of("export") {
+        let fps: f64 = matches.value_of("fps").unwrap().parse().expect("Invalid --fps");
+        let width: i32 = matches
+            .value_of("width")
+            .unwrap()
+            .parse()
+            .expect("Invalid --width");
+        let height: i32 = matches
+            .value_of("height")
+            .unwrap()
+            .parse()
+            .expect("Invalid --height");
+
+        export_frames(filename, pattern, fps, width, height);
+        return;
+    }
+
+    if let Some(out) = matches.value_of("encode") {
+        let fps: f64 = matches.value_of("fps").unwrap().parse().expect("Invalid --fps");
+        let width: i32 = matches
+            .value_of("width")
+            .unwrap()
+            .parse()
+            .expect("Invalid --width");
+        let height: i32 = matches
+            .value_of("height")
+            .unwrap()
+            .parse()
+            .expect("Invalid --height");
+
+        encode_video(filename, Path::new(out), fps, width, height);
+        return;
+    }
+
+    let vsync = matches.is_present("vsync");
+
     // Calculate the right logical size of the window.
     let event_loop = EventLoop::new();
     let logical_window_size = LogicalSize::new(WIDTH as f64, HEIGHT as f64);
 
     // Open a window.
     let window_builder = WindowBuilder::new()
-        .with_title("Minimal example")
+        .with_title("Lottie Viewer")
         .with_inner_size(logical_window_size);
     #[cfg(windows)]
     let window_builder = window_builder.with_drag_and_drop(false);
 
-    let gl_context = ContextBuilder::new()
-        .with_gl(GlRequest::GlThenGles {
-            opengl_version: (4, 6),
-            opengles_version: (3, 1),
+    let template = ConfigTemplateBuilder::new();
+    let display_builder = DisplayBuilder::new().with_window_builder(Some(window_builder));
+
+    let (window, gl_config) = display_builder
+        .build(&event_loop, template, |configs| {
+            configs
+                .reduce(|accum, config| {
+                    if config.num_samples() < accum.num_samples() {
+                        config
+                    } else {
+                        accum
+                    }
+                })
+                .unwrap()
         })
-        .with_multisampling(0)
-        .with_hardware_acceleration(Some(true))
-        .build_windowed(window_builder, &event_loop)
         .unwrap();
+    let mut window = window.unwrap();
+
+    let raw_window_handle = window.raw_window_handle();
+    let gl_display = gl_config.display();
+
+    let context_attributes = ContextAttributesBuilder::new()
+        .with_context_api(ContextApi::OpenGl(None))
+        .build(Some(raw_window_handle));
+    let not_current_gl_context = unsafe {
+        gl_display
+            .create_context(&gl_config, &context_attributes)
+            .unwrap()
+    };
+
+    fn surface_attrs(window: &Window) -> SurfaceAttributesBuilder<WindowSurface> {
+        let size = window.inner_size();
+        SurfaceAttributesBuilder::<WindowSurface>::new().build(
+            window.raw_window_handle(),
+            NonZeroU32::new(size.width.max(1)).unwrap(),
+            NonZeroU32::new(size.height.max(1)).unwrap(),
+        )
+    }
 
-    // Load OpenGL, and make the context current.
-    let gl_context = unsafe { gl_context.make_current().unwrap() };
+    // Holds the window surface and its current GL context; torn down on `Suspended` (where,
+    // on Android, the backing window is gone) and rebuilt on the following `Resumed`.
+    struct GlState {
+        surface: glutin::surface::Surface<WindowSurface>,
+        context: PossiblyCurrentContext,
+    }
+
+    fn make_gl_state(
+        gl_display: &glutin::display::Display,
+        gl_config: &glutin::config::Config,
+        window: &Window,
+        not_current_context: NotCurrentContext,
+        vsync: bool,
+    ) -> GlState {
+        let surface = unsafe {
+            gl_display
+                .create_window_surface(gl_config, &surface_attrs(window))
+                .unwrap()
+        };
+        let context = not_current_context.make_current(&surface).unwrap();
+        surface
+            .set_swap_interval(
+                &context,
+                if vsync {
+                    SwapInterval::Wait(NonZeroU32::new(1).unwrap())
+                } else {
+                    SwapInterval::DontWait
+                },
+            )
+            .unwrap();
+        GlState { surface, context }
+    }
 
-    gl::load_with(|name| gl_context.get_proc_address(name));
+    let mut gl_state = Some(make_gl_state(
+        &gl_display,
+        &gl_config,
+        &window,
+        not_current_gl_context,
+        vsync,
+    ));
+    // Set only while suspended: the context handed back by `make_not_current`, waiting to be
+    // made current again over a freshly recreated surface on the next `Resumed`.
+    let mut not_current_context: Option<NotCurrentContext> = None;
+
+    gl::load_with(|name| {
+        let name = CString::new(name).unwrap();
+        gl_display.get_proc_address(&name) as *const _
+    });
 
     let mut gr_context = skia::gpu::Context::new_gl(None, None).unwrap();
 
@@ -72,19 +488,18 @@ fn main() {
     };
 
     fn create_surface(
-        windowed_context: &WindowedContext,
+        window: &Window,
         fb_info: &FramebufferInfo,
         gr_context: &mut skia::gpu::Context,
     ) -> skia::Surface {
-        let pixel_format = windowed_context.get_pixel_format();
-        let size = windowed_context.window().inner_size();
+        let size = window.inner_size();
         let backend_render_target = BackendRenderTarget::new_gl(
             (
                 size.width.try_into().unwrap(),
                 size.height.try_into().unwrap(),
             ),
-            pixel_format.multisampling.map(|s| s.try_into().unwrap()),
-            pixel_format.stencil_bits.try_into().unwrap(),
+            Some(0),
+            8,
             *fb_info,
         );
         Surface::from_backend_render_target(
@@ -98,39 +513,43 @@ fn main() {
         .unwrap()
     }
 
-    let mut surface = create_surface(&gl_context, &fb_info, &mut gr_context);
-    let sf = gl_context.window().scale_factor() as f32;
+    let mut surface = create_surface(&window, &fb_info, &mut gr_context);
+    let sf = window.scale_factor() as f32;
     surface.canvas().scale((sf, sf));
 
     let mut last = time::Instant::now();
-
-    let mut now = time::Instant::now();
-    let start = now;
+    let mut now = last;
 
     let num_frames = 1000;
     let mut times = Vec::with_capacity(num_frames);
     times.push(now - last);
 
-    last = now;
+    let mut to_render = load_renderable(filename);
+    let mut playback = Playback::new();
 
-    let mut file = std::fs::File::open(filename).unwrap();
-    let mut to_render = match filename
-        .extension()
-        .map(|e| e.to_string_lossy().to_ascii_lowercase())
-        .as_ref()
-        .map(|s| &s[..])
-    {
-        Some("json") | Some("lottie") => skia::animation::Animation::read(&mut file)
-            .map(Either::Left)
-            .expect("Failed to open lottie file"),
-        Some("svg") => skia::svg::SvgDom::read(&mut file)
-            .map(Either::Right)
-            .expect("Failed to open lottie file"),
-        other => panic!("Unrecognized filetype: {:?}", other),
-    };
+    let show_overlay = matches.is_present("overlay");
+    let mut overlay = show_overlay.then(|| {
+        let size = window.inner_size();
+        Overlay::new(OverlayProps {
+            width: size.width as f32,
+            height: size.height as f32,
+            paused: playback.paused,
+            time: playback.time,
+            duration: 0.0,
+            fps: 0.0,
+            drag_over: false,
+        })
+    });
+    let mut cursor_pos = (0.0_f32, 0.0_f32);
+    let mut scrubbing = false;
+    let mut drag_over = false;
 
     event_loop.run(move |event, _, control_flow| {
-        *control_flow = ControlFlow::Poll;
+        *control_flow = if vsync {
+            ControlFlow::Wait
+        } else {
+            ControlFlow::Poll
+        };
 
         if times.len() >= num_frames {
             let avg = times.drain(..).take(num_frames).sum::<time::Duration>() / num_frames as u32;
@@ -142,6 +561,27 @@ fn main() {
         }
 
         match event {
+            Event::Resumed => {
+                // On desktop this also fires once at startup, when `gl_state` is already
+                // `Some` from the setup above, so this is a no-op there. On Android the
+                // GL surface was torn down in `Suspended`, so recreate it (and the Skia
+                // surface over it) on the existing window here before resuming rendering.
+                if gl_state.is_none() {
+                    let context = not_current_context
+                        .take()
+                        .expect("Resumed without a context to resume from");
+                    gl_state = Some(make_gl_state(&gl_display, &gl_config, &window, context, vsync));
+                    surface = create_surface(&window, &fb_info, &mut gr_context);
+                }
+                window.request_redraw();
+            }
+            Event::Suspended => {
+                // Drop the surface and demote the context so it can be handed to a new
+                // surface in `Resumed`; on Android, rendering must stop entirely until then.
+                if let Some(gl) = gl_state.take() {
+                    not_current_context = Some(gl.context.make_not_current().unwrap());
+                }
+            }
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested,
                 ..
@@ -164,13 +604,164 @@ fn main() {
                 event: WindowEvent::Resized(physical_size),
                 ..
             } => {
-                gl_context.resize(physical_size);
-                surface = create_surface(&gl_context, &fb_info, &mut gr_context);
+                if physical_size.width > 0 && physical_size.height > 0 {
+                    if let Some(gl) = &gl_state {
+                        gl.surface.resize(
+                            &gl.context,
+                            NonZeroU32::new(physical_size.width).unwrap(),
+                            NonZeroU32::new(physical_size.height).unwrap(),
+                        );
+                        surface = create_surface(&window, &fb_info, &mut gr_context);
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorMoved { position, .. },
+                ..
+            } => {
+                cursor_pos = (position.x as f32, position.y as f32);
+                if scrubbing {
+                    if let (Some(overlay), Either::Left(animation)) = (&overlay, &to_render) {
+                        if let Some(frac) = overlay.hit_test_scrubber(cursor_pos.0, cursor_pos.1) {
+                            playback.seek(frac as f64 * animation.duration(), animation.duration());
+                            window.request_redraw();
+                        }
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        state,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            } => {
+                if let (Some(overlay), Either::Left(animation)) = (&overlay, &to_render) {
+                    match state {
+                        ElementState::Pressed => {
+                            if overlay.hit_test_button(cursor_pos.0, cursor_pos.1) {
+                                playback.paused = !playback.paused;
+                                window.request_redraw();
+                            } else if let Some(frac) =
+                                overlay.hit_test_scrubber(cursor_pos.0, cursor_pos.1)
+                            {
+                                playback.seek(frac as f64 * animation.duration(), animation.duration());
+                                scrubbing = true;
+                                window.request_redraw();
+                            }
+                        }
+                        ElementState::Released => scrubbing = false,
+                    }
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::HoveredFile(_),
+                ..
+            } => {
+                drag_over = true;
+                window.request_redraw();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::HoveredFileCancelled,
+                ..
+            } => {
+                drag_over = false;
+                window.request_redraw();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::DroppedFile(path),
+                ..
+            } => {
+                drag_over = false;
+                match try_load_dropped_file(&path) {
+                    Ok(renderable) => {
+                        to_render = renderable;
+                        playback = Playback::new();
+                    }
+                    Err(e) => eprintln!("Failed to load dropped file {:?}: {}", path, e),
+                }
+                window.request_redraw();
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(VirtualKeyCode::F11),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                window.set_fullscreen(if window.fullscreen().is_some() {
+                    None
+                } else {
+                    Some(Fullscreen::Borderless(None))
+                });
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                if let Either::Left(animation) = &to_render {
+                    let dur = animation.duration();
+                    let fps = animation.fps();
+                    match key {
+                        VirtualKeyCode::Space => playback.paused = !playback.paused,
+                        VirtualKeyCode::Left => playback.step_frames(-1.0, fps, dur),
+                        VirtualKeyCode::Right => playback.step_frames(1.0, fps, dur),
+                        VirtualKeyCode::Up => playback.nudge_speed(0.1),
+                        VirtualKeyCode::Down => playback.nudge_speed(-0.1),
+                        VirtualKeyCode::Home => playback.seek(0.0, dur),
+                        VirtualKeyCode::L => playback.looping = !playback.looping,
+                        _ => {}
+                    }
+                    window.request_redraw();
+                }
             }
             Event::RedrawRequested(_) => {
+                let Some(gl) = &gl_state else {
+                    // Suspended: no surface to render into until the next `Resumed`.
+                    return;
+                };
+
+                now = time::Instant::now();
+                let dt = (now - last).as_secs_f64();
+                last = now;
+
                 if let Either::Left(animation) = &mut to_render {
-                    let dur = animation.duration();
-                    animation.seek_time::<()>((now - start).as_secs_f64() % dur);
+                    playback.advance(dt, animation.duration());
+                    animation.seek_time::<()>(playback.time);
+                }
+
+                if let Some(overlay) = &mut overlay {
+                    let size = window.inner_size();
+                    let (duration, fps) = match &to_render {
+                        Either::Left(animation) => (animation.duration(), animation.fps()),
+                        Either::Right(_) => (0.0, 0.0),
+                    };
+                    overlay.render(OverlayProps {
+                        width: size.width as f32,
+                        height: size.height as f32,
+                        paused: playback.paused,
+                        time: playback.time,
+                        duration,
+                        fps,
+                        drag_over,
+                    });
                 }
 
                 {
@@ -182,20 +773,76 @@ fn main() {
                         Either::Right(svg) => svg.render(canvas),
                     }
 
+                    if let Some(overlay) = &overlay {
+                        overlay.paint(canvas);
+                    }
+
                     canvas.flush();
                 }
 
-                gl_context.swap_buffers().unwrap();
+                gl.surface.swap_buffers(&gl.context).unwrap();
 
-                now = time::Instant::now();
-                let this_dt = now - last;
-                times.push(this_dt);
-                last = now;
+                times.push(time::Duration::from_secs_f64(dt));
             }
             Event::MainEventsCleared => {
-                gl_context.window().request_redraw();
+                window.request_redraw();
             }
             _ => {}
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_frame_path_zero_padded() {
+        assert_eq!(format_frame_path("frame_%04d.png", 7), "frame_0007.png");
+    }
+
+    #[test]
+    fn format_frame_path_unpadded() {
+        assert_eq!(format_frame_path("frame_%d.png", 7), "frame_7.png");
+    }
+
+    #[test]
+    fn format_frame_path_no_placeholder() {
+        assert_eq!(format_frame_path("frame.png", 7), "frame.png");
+    }
+
+    #[test]
+    fn playback_seek_wraps_when_looping() {
+        let mut playback = Playback::new();
+        playback.looping = true;
+        playback.seek(12.0, 10.0);
+        assert_eq!(playback.time, 2.0);
+    }
+
+    #[test]
+    fn playback_seek_clamps_when_not_looping() {
+        let mut playback = Playback::new();
+        playback.looping = false;
+        playback.seek(12.0, 10.0);
+        assert_eq!(playback.time, 10.0);
+
+        playback.seek(-5.0, 10.0);
+        assert_eq!(playback.time, 0.0);
+    }
+
+    #[test]
+    fn playback_advance_is_noop_when_paused() {
+        let mut playback = Playback::new();
+        playback.paused = true;
+        playback.advance(5.0, 10.0);
+        assert_eq!(playback.time, 0.0);
+    }
+
+    #[test]
+    fn playback_step_frames_uses_fps() {
+        let mut playback = Playback::new();
+        playback.looping = false;
+        playback.step_frames(5.0, 10.0, 10.0);
+        assert_eq!(playback.time, 0.5);
+    }
+}
@@ -0,0 +1,327 @@
+//! A small on-canvas control overlay (play/pause, scrubber, time/fps readout, drag-and-drop
+//! file target) composited over the animation on the same Skia surface the viewer already
+//! owns, in the style of the dioxus+Skia renderers used by viewbuilder and freya.
+//!
+//! Layout and widget state aren't recomputed ad hoc in the `RedrawRequested` arm; instead a
+//! tiny dioxus [`VirtualDom`] describes the overlay as a handful of `div`s carrying
+//! `skia-*` attributes (kind/bounds/label/flags), and the `apply` function folds the
+//! `Mutation`s produced by diffing it into a flat [`Widget`] list. [`Overlay::paint`] turns
+//! that list into Skia draw calls on the existing canvas, and [`Overlay::hit_test_scrubber`]/
+//! [`Overlay::hit_test_button`] are used to route `CursorMoved`/`MouseInput` back into the
+//! widget (and ultimately into `Playback`) they landed on.
+
+use dioxus::prelude::{dioxus_elements, rsx};
+use dioxus_core::{BorrowedAttributeValue, ElementId, Mutation, Scope, VirtualDom};
+use skia_safe::{Canvas, Color, Font, FontStyle, Paint, Point, Rect, Typeface};
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/// Props driving a single render of the overlay markup; `overlay_ui` below is re-rendered
+/// whenever any of these change (playback toggled, scrubber dragged, window resized).
+#[derive(Clone, PartialEq)]
+pub struct OverlayProps {
+    pub width: f32,
+    pub height: f32,
+    pub paused: bool,
+    pub time: f64,
+    pub duration: f64,
+    pub fps: f64,
+    pub drag_over: bool,
+}
+
+const BAR_HEIGHT: f32 = 36.0;
+const BUTTON_WIDTH: f32 = 48.0;
+const THUMB_WIDTH: f32 = 10.0;
+const MARGIN: f32 = 8.0;
+
+// `"skia-kind": "button"` (a plain string literal) compiles into the dioxus `Template` as a
+// static attribute and is never emitted as a `Mutation::SetAttribute` — only interpolated
+// (`"{...}"`) attrs come through as edits. Routing these through a `{KIND_*}` interpolation
+// forces dioxus to treat them as dynamic so `apply` actually observes them.
+const KIND_BUTTON: &str = "button";
+const KIND_TRACK: &str = "track";
+const KIND_THUMB: &str = "thumb";
+const KIND_READOUT: &str = "readout";
+const KIND_DROPZONE: &str = "dropzone";
+
+fn overlay_ui(cx: Scope<OverlayProps>) -> dioxus_core::Element {
+    let props = cx.props;
+    let track_x0 = MARGIN + BUTTON_WIDTH + MARGIN;
+    let track_x1 = props.width - MARGIN;
+    let track_y = props.height - BAR_HEIGHT + (BAR_HEIGHT - 6.0) / 2.0;
+    let frac = if props.duration > 0.0 {
+        (props.time / props.duration).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let thumb_x = track_x0 + frac as f32 * (track_x1 - track_x0) - THUMB_WIDTH / 2.0;
+
+    cx.render(rsx! {
+        div {
+            "skia-kind": "{KIND_BUTTON}",
+            "skia-bounds": "{MARGIN},{props.height - BAR_HEIGHT},{BUTTON_WIDTH},{BAR_HEIGHT}",
+            "skia-paused": "{props.paused}",
+        }
+        div {
+            "skia-kind": "{KIND_TRACK}",
+            "skia-bounds": "{track_x0},{track_y},{track_x1 - track_x0},6.0",
+        }
+        div {
+            "skia-kind": "{KIND_THUMB}",
+            "skia-bounds": "{thumb_x},{props.height - BAR_HEIGHT},{THUMB_WIDTH},{BAR_HEIGHT}",
+        }
+        div {
+            "skia-kind": "{KIND_READOUT}",
+            "skia-origin": "{MARGIN},{16.0}",
+            "skia-label": "{format_readout(props.time, props.duration, props.fps)}",
+        }
+        div {
+            "skia-kind": "{KIND_DROPZONE}",
+            "skia-bounds": "0.0,0.0,{props.width},{props.height}",
+            "skia-active": "{props.drag_over}",
+        }
+    })
+}
+
+fn format_readout(time: f64, duration: f64, fps: f64) -> String {
+    format!("{:5.2}s / {:5.2}s  {:3.0} fps", time, duration, fps)
+}
+
+/// One paintable, hit-testable control, as synced from the overlay's dioxus markup.
+#[derive(Clone, Debug)]
+enum Widget {
+    Button { bounds: Rect, paused: bool },
+    Track { bounds: Rect },
+    Thumb { bounds: Rect },
+    Readout { origin: Point, label: String },
+    DropZone { bounds: Rect, active: bool },
+}
+
+fn widget_from_attrs(kind: &str, attrs: &HashMap<String, String>) -> Option<Widget> {
+    let bounds = |attrs: &HashMap<String, String>| -> Option<Rect> {
+        let parts: Vec<f32> = attrs
+            .get("skia-bounds")?
+            .split(',')
+            .map(|s| s.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        let [x, y, w, h]: [f32; 4] = parts.try_into().ok()?;
+        Some(Rect::from_xywh(x, y, w, h))
+    };
+
+    match kind {
+        "button" => Some(Widget::Button {
+            bounds: bounds(attrs)?,
+            paused: attrs.get("skia-paused").map(|s| s == "true").unwrap_or(false),
+        }),
+        "track" => Some(Widget::Track {
+            bounds: bounds(attrs)?,
+        }),
+        "thumb" => Some(Widget::Thumb {
+            bounds: bounds(attrs)?,
+        }),
+        "readout" => {
+            let mut parts = attrs.get("skia-origin")?.split(',');
+            let x: f32 = parts.next()?.trim().parse().ok()?;
+            let y: f32 = parts.next()?.trim().parse().ok()?;
+            Some(Widget::Readout {
+                origin: Point::new(x, y),
+                label: attrs.get("skia-label").cloned().unwrap_or_default(),
+            })
+        }
+        "dropzone" => Some(Widget::DropZone {
+            bounds: bounds(attrs)?,
+            active: attrs.get("skia-active").map(|s| s == "true").unwrap_or(false),
+        }),
+        _ => None,
+    }
+}
+
+/// Owns the dioxus `VirtualDom` for the overlay markup and the flat widget list painting and
+/// hit-testing actually work from. `render` rebuilds the dom from new `OverlayProps` and
+/// folds the resulting mutations into `widgets`; nothing here touches the GPU directly.
+pub struct Overlay {
+    dom: VirtualDom,
+    // Per-element scratch attributes, keyed by the id dioxus assigns each node; folded into
+    // `widgets` once a render pass's mutations have all been applied.
+    attrs: HashMap<ElementId, HashMap<String, String>>,
+    kinds: HashMap<ElementId, String>,
+    // Tracks the order elements were first seen in, so painting follows the markup order
+    // declared in `overlay_ui` (e.g. the thumb on top of the track) instead of whatever
+    // order `kinds`/`attrs` (both HashMaps) happen to iterate in.
+    order: Vec<ElementId>,
+    widgets: Vec<Widget>,
+}
+
+impl Overlay {
+    pub fn new(props: OverlayProps) -> Self {
+        let mut overlay = Overlay {
+            dom: VirtualDom::new_with_props(overlay_ui, props),
+            attrs: HashMap::new(),
+            kinds: HashMap::new(),
+            order: Vec::new(),
+            widgets: Vec::new(),
+        };
+        let dioxus_core::Mutations { edits, .. } = overlay.dom.rebuild();
+        apply(
+            &mut overlay.attrs,
+            &mut overlay.kinds,
+            &mut overlay.order,
+            &mut overlay.widgets,
+            edits,
+        );
+        overlay
+    }
+
+    /// Rebuilds the overlay markup for `props` and re-derives the widget list used by
+    /// `paint`/`hit_test_scrubber`/`hit_test_button`. The root dom is recreated each call (this overlay has only a
+    /// handful of nodes, so a full rebuild is cheap); a retained dom with prop diffing would
+    /// be worth it for a larger control surface.
+    pub fn render(&mut self, props: OverlayProps) {
+        self.attrs.clear();
+        self.kinds.clear();
+        self.order.clear();
+        self.dom = VirtualDom::new_with_props(overlay_ui, props);
+        let dioxus_core::Mutations { edits, .. } = self.dom.rebuild();
+        apply(
+            &mut self.attrs,
+            &mut self.kinds,
+            &mut self.order,
+            &mut self.widgets,
+            edits,
+        );
+    }
+
+    /// Draws every synced widget onto `canvas` in its own local (non-scaled-by-playback)
+    /// coordinate space, on top of whatever the animation already rendered this frame.
+    pub fn paint(&self, canvas: &Canvas) {
+        let mut fill = Paint::default();
+        fill.set_anti_alias(true);
+
+        let font = Font::new(
+            Typeface::from_name("sans-serif", FontStyle::default())
+                .unwrap_or_else(|| Typeface::default()),
+            14.0,
+        );
+
+        for widget in &self.widgets {
+            match widget {
+                Widget::Button { bounds, paused } => {
+                    fill.set_color(Color::from_argb(0xc0, 0x20, 0x20, 0x20));
+                    canvas.draw_round_rect(bounds, 4.0, 4.0, &fill);
+
+                    fill.set_color(Color::WHITE);
+                    let cx = bounds.center_x();
+                    let cy = bounds.center_y();
+                    if *paused {
+                        // Play glyph: a simple triangle.
+                        let mut path = skia_safe::Path::new();
+                        path.move_to((cx - 6.0, cy - 8.0));
+                        path.line_to((cx - 6.0, cy + 8.0));
+                        path.line_to((cx + 8.0, cy));
+                        path.close();
+                        canvas.draw_path(&path, &fill);
+                    } else {
+                        // Pause glyph: two bars.
+                        canvas.draw_rect(Rect::from_xywh(cx - 7.0, cy - 8.0, 5.0, 16.0), &fill);
+                        canvas.draw_rect(Rect::from_xywh(cx + 2.0, cy - 8.0, 5.0, 16.0), &fill);
+                    }
+                }
+                Widget::Track { bounds } => {
+                    fill.set_color(Color::from_argb(0x80, 0xff, 0xff, 0xff));
+                    canvas.draw_round_rect(bounds, 3.0, 3.0, &fill);
+                }
+                Widget::Thumb { bounds } => {
+                    fill.set_color(Color::WHITE);
+                    canvas.draw_round_rect(bounds, 3.0, 3.0, &fill);
+                }
+                Widget::Readout { origin, label } => {
+                    fill.set_color(Color::WHITE);
+                    canvas.draw_str(label, *origin, &font, &fill);
+                }
+                Widget::DropZone { bounds, active } => {
+                    if *active {
+                        let mut stroke = Paint::default();
+                        stroke.set_anti_alias(true);
+                        stroke.set_style(skia_safe::PaintStyle::Stroke);
+                        stroke.set_stroke_width(4.0);
+                        stroke.set_color(Color::from_argb(0xff, 0x4a, 0x9e, 0xff));
+                        canvas.draw_rect(bounds.with_inset((2.0, 2.0)), &stroke);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the scrubber fraction `[0, 1]` that a click/drag at `(x, y)` should seek to,
+    /// if it landed on the track or thumb; `None` if it hit something else (or nothing).
+    pub fn hit_test_scrubber(&self, x: f32, y: f32) -> Option<f32> {
+        let track = self.widgets.iter().find_map(|w| match w {
+            Widget::Track { bounds } => Some(*bounds),
+            _ => None,
+        })?;
+
+        if track.with_outset((0.0, BAR_HEIGHT / 2.0)).contains(Point::new(x, y)) {
+            Some(((x - track.left) / track.width()).clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+
+    /// True if `(x, y)` landed on the play/pause button.
+    pub fn hit_test_button(&self, x: f32, y: f32) -> bool {
+        self.widgets.iter().any(|w| match w {
+            Widget::Button { bounds, .. } => bounds.contains(Point::new(x, y)),
+            _ => false,
+        })
+    }
+}
+
+// Folds `edits` (the output of diffing `overlay_ui`'s markup) into `attrs`/`kinds`/`order`,
+// then rebuilds `widgets` from them. A free function, not an `Overlay` method, so it only
+// borrows these four fields and not `dom` too — `dom.rebuild()`'s returned `Mutations`
+// borrows `dom` for as long as `edits` is alive, which a method taking `&mut self` can't see
+// is disjoint from the fields actually touched here.
+fn apply(
+    attrs: &mut HashMap<ElementId, HashMap<String, String>>,
+    kinds: &mut HashMap<ElementId, String>,
+    order: &mut Vec<ElementId>,
+    widgets: &mut Vec<Widget>,
+    edits: Vec<Mutation>,
+) {
+    for edit in edits {
+        match edit {
+            Mutation::SetAttribute {
+                name, value, id, ..
+            } => {
+                if name == "skia-kind" {
+                    if let BorrowedAttributeValue::Text(kind) = value {
+                        if !kinds.contains_key(&id) {
+                            order.push(id);
+                        }
+                        kinds.insert(id, kind.to_string());
+                    }
+                } else if let BorrowedAttributeValue::Text(text) = value {
+                    attrs
+                        .entry(id)
+                        .or_default()
+                        .insert(name.to_string(), text.to_string());
+                }
+            }
+            Mutation::Remove { id } => {
+                attrs.remove(&id);
+                kinds.remove(&id);
+                order.retain(|existing| *existing != id);
+            }
+            _ => {}
+        }
+    }
+
+    let empty = HashMap::new();
+    *widgets = order
+        .iter()
+        .filter_map(|id| {
+            let kind = kinds.get(id)?;
+            widget_from_attrs(kind, attrs.get(id).unwrap_or(&empty))
+        })
+        .collect();
+}